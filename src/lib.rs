@@ -1,16 +1,102 @@
 #![deny(missing_docs)]
 #![doc = include_str!("../README.md")]
+use std::collections::btree_map;
 use std::collections::BTreeMap;
 use std::fmt::Display;
+use std::marker::PhantomData;
 
-/// A fixed-size indexed vector that maps indices to values.
+/// A trait for types that can be used as indices into a [`FixedIndexVec`].
 ///
-/// It provides a fixed-size vector-like data structure that can store values based on its
-/// associated index.
+/// Implementing this trait for a newtype (rather than using the default `usize`) gives
+/// compile-time separation between the index spaces of different collections, so an index
+/// that belongs to a `FixedIndexVec<User>` can't accidentally be used to access a
+/// `FixedIndexVec<Order>`. The [`define_index_type!`] macro generates such a newtype.
+pub trait Idx: Copy {
+    /// Converts a `usize` into this index type.
+    fn from_usize(index: usize) -> Self;
+
+    /// Converts this index type into a `usize`.
+    fn into_usize(self) -> usize;
+}
+
+impl Idx for usize {
+    fn from_usize(index: usize) -> Self {
+        index
+    }
+
+    fn into_usize(self) -> usize {
+        self
+    }
+}
+
+/// Defines a `#[repr(transparent)]` newtype around `usize` that implements [`Idx`], so it can
+/// be used as the index type of a [`FixedIndexVec`].
+///
+/// # Examples
+///
+/// ```
+/// use fixed_index_vec::{define_index_type, FixedIndexVec};
+///
+/// define_index_type! { pub struct UserId; }
+///
+/// let mut users: FixedIndexVec<String, UserId> = FixedIndexVec::new();
+/// let id = users.push("Alice".to_string());
+/// assert_eq!(users.get(id), Some(&"Alice".to_string()));
+/// ```
+#[macro_export]
+macro_rules! define_index_type {
+    ($(#[$meta:meta])* $vis:vis struct $name:ident;) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+        #[repr(transparent)]
+        $vis struct $name(usize);
+
+        impl $crate::Idx for $name {
+            fn from_usize(index: usize) -> Self {
+                $name(index)
+            }
+
+            fn into_usize(self) -> usize {
+                self.0
+            }
+        }
+
+        impl From<usize> for $name {
+            fn from(index: usize) -> Self {
+                $name(index)
+            }
+        }
+
+        impl From<$name> for usize {
+            fn from(index: $name) -> Self {
+                index.0
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+    };
+}
+
+/// An indexed vector that maps indices to values, with an opt-in fixed capacity.
+///
+/// It provides a vector-like data structure that can store values based on its associated
+/// index.
 /// Each value is associated with a unique index in the map.
 /// The values can be
 /// accessed, inserted, and removed using the index as the identifier.
 ///
+/// `FixedIndexVec::new()` is unbounded: it grows for as long as values are pushed onto it. Use
+/// `FixedIndexVec::with_capacity` to impose a real fixed-size bound, past which `push` panics
+/// and `try_push` returns the value back to the caller.
+///
+/// The index type defaults to `usize`, but can be set to any type implementing [`Idx`]
+/// (typically one generated by [`define_index_type!`]) to get compile-time separation
+/// between the index spaces of different collections.
+///
 /// # Examples
 ///
 /// ```
@@ -35,23 +121,24 @@ use std::fmt::Display;
 /// - Index notations are supported (eg. `vec[0]`), however, accessing an index that does not
 ///  exist will panic.
 #[derive(Clone, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub struct FixedIndexVec<T> {
+pub struct FixedIndexVec<T, I = usize> {
     map: BTreeMap<usize, T>,
     next_index: usize,
+    capacity: Option<usize>,
+    _marker: PhantomData<I>,
 }
 
-impl<T: Display> Display for FixedIndexVec<T> {
+impl<T: Display, I: Idx> Display for FixedIndexVec<T, I> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut s = String::new();
         for (i, v) in self.iter() {
-            s.push_str(&format!("{}: {}\n", i, v));
+            s.push_str(&format!("{}: {}\n", i.into_usize(), v));
         }
         write!(f, "{}", s)
     }
 }
 
-impl<T> FixedIndexVec<T> {
+impl<T, I: Idx> FixedIndexVec<T, I> {
     /// Creates an empty `FixedIndexVec`.
     ///
     /// The internal storage will not allocate until elements are pushed onto it.
@@ -62,14 +149,71 @@ impl<T> FixedIndexVec<T> {
     /// use fixed_index_vec::FixedIndexVec;
     /// let mut vec: FixedIndexVec<i32> = FixedIndexVec::new();
     /// ```
-    pub fn new() -> FixedIndexVec<T> {
+    pub fn new() -> FixedIndexVec<T, I> {
         FixedIndexVec {
             map: BTreeMap::new(),
             next_index: 0,
+            capacity: None,
+            _marker: PhantomData,
         }
     }
 
-    /// Inserts an element at the end of the `FixedIndexVec`.
+    /// Creates an empty `FixedIndexVec` with a fixed capacity.
+    ///
+    /// While the `FixedIndexVec` holds `capacity` elements, `push` will panic and `try_push` will
+    /// return the value back to the caller instead of inserting it. Removing an element makes
+    /// room for another.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_index_vec::FixedIndexVec;
+    /// let mut vec: FixedIndexVec<i32> = FixedIndexVec::with_capacity(2);
+    /// vec.push(1);
+    /// vec.push(2);
+    /// assert!(vec.try_push(3).is_err());
+    /// ```
+    pub fn with_capacity(capacity: usize) -> FixedIndexVec<T, I> {
+        FixedIndexVec {
+            map: BTreeMap::new(),
+            next_index: 0,
+            capacity: Some(capacity),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the capacity of the `FixedIndexVec`, or `None` if it is unbounded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_index_vec::FixedIndexVec;
+    /// let vec: FixedIndexVec<i32> = FixedIndexVec::with_capacity(2);
+    /// assert_eq!(vec.capacity(), Some(2));
+    ///
+    /// let vec: FixedIndexVec<i32> = FixedIndexVec::new();
+    /// assert_eq!(vec.capacity(), None);
+    /// ```
+    pub fn capacity(&self) -> Option<usize> {
+        self.capacity
+    }
+
+    /// Returns the number of additional elements that can be pushed before the
+    /// `FixedIndexVec` is at capacity, or `None` if it is unbounded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_index_vec::FixedIndexVec;
+    /// let mut vec: FixedIndexVec<i32> = FixedIndexVec::with_capacity(2);
+    /// vec.push(1);
+    /// assert_eq!(vec.available(), Some(1));
+    /// ```
+    pub fn available(&self) -> Option<usize> {
+        self.capacity.map(|capacity| capacity - self.len())
+    }
+
+    /// Inserts an element at the end of the `FixedIndexVec`, returning its assigned index.
     ///
     /// # Panics
     ///
@@ -86,13 +230,42 @@ impl<T> FixedIndexVec<T> {
     /// assert_eq!(vec[0], 1);
     /// assert_eq!(vec[1], 2);
     /// ```
-    pub fn push(&mut self, value: T) {
-        self.map.insert(self.next_index, value);
+    pub fn push(&mut self, value: T) -> I {
+        match self.try_push(value) {
+            Ok(index) => index,
+            Err(_) => panic!("FixedIndexVec is at capacity"),
+        }
+    }
+
+    /// Attempts to insert an element at the end of the `FixedIndexVec`, returning the
+    /// assigned index on success.
+    ///
+    /// Unlike `push`, this never panics: if the `FixedIndexVec` is at capacity, the value
+    /// is handed back to the caller instead of being inserted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_index_vec::FixedIndexVec;
+    ///
+    /// let mut vec: FixedIndexVec<i32> = FixedIndexVec::with_capacity(1);
+    /// assert_eq!(vec.try_push(1), Ok(0));
+    /// assert_eq!(vec.try_push(2), Err(2));
+    /// ```
+    pub fn try_push(&mut self, value: T) -> Result<I, T> {
+        if let Some(capacity) = self.capacity {
+            if self.map.len() >= capacity {
+                return Err(value);
+            }
+        }
+        let index = self.next_index;
+        self.map.insert(index, value);
         self.next_index += 1;
+        Ok(I::from_usize(index))
     }
 
     /// Alias for `push`.
-    /// Inserts an element at the end of the `FixedIndexVec`.
+    /// Inserts an element at the end of the `FixedIndexVec`, returning its assigned index.
     ///
     /// # Panics
     ///
@@ -109,8 +282,8 @@ impl<T> FixedIndexVec<T> {
     /// assert_eq!(vec[0], 1);
     /// assert_eq!(vec[1], 2);
     /// ```
-    pub fn insert(&mut self, value: T) {
-        self.push(value);
+    pub fn insert(&mut self, value: T) -> I {
+        self.push(value)
     }
 
     /// Removes the element at the given index, if it exists, returning it or `None` if it does not exist.
@@ -131,8 +304,8 @@ impl<T> FixedIndexVec<T> {
     ///
     /// Unlike `Vec::remove`, this does not shift elements after the removed element.
     /// If index >= length, this returns `None`, the same as if the element did not exist.
-    pub fn remove(&mut self, index: usize) -> Option<T> {
-        self.map.remove(&index)
+    pub fn remove(&mut self, index: I) -> Option<T> {
+        self.map.remove(&index.into_usize())
     }
 
     /// Returns a reference to the element at the given index,
@@ -149,8 +322,25 @@ impl<T> FixedIndexVec<T> {
     /// assert_eq!(vec.get(0), Some(&1));
     /// assert_eq!(vec.get(2), None);
     /// ```
-    pub fn get(&self, index: usize) -> Option<&T> {
-        self.map.get(&index)
+    pub fn get(&self, index: I) -> Option<&T> {
+        self.map.get(&index.into_usize())
+    }
+
+    /// Returns a mutable reference to the element at the given index,
+    /// if it exists, or `None` if it does not exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_index_vec::FixedIndexVec;
+    ///
+    /// let mut vec = FixedIndexVec::new();
+    /// vec.push(1);
+    /// *vec.get_mut(0).unwrap() += 1;
+    /// assert_eq!(vec.get(0), Some(&2));
+    /// ```
+    pub fn get_mut(&mut self, index: I) -> Option<&mut T> {
+        self.map.get_mut(&index.into_usize())
     }
 
     /// An iterator visiting all elements in ascending order of their indices.
@@ -169,8 +359,79 @@ impl<T> FixedIndexVec<T> {
     /// assert_eq!(iter.next(), Some((2, &3)));
     /// assert_eq!(iter.next(), None);
     /// ```
-    pub fn iter(&self) -> impl Iterator<Item = (usize, &T)> {
-        self.map.iter().map(|(i, v)| (*i, v))
+    pub fn iter(&self) -> impl Iterator<Item = (I, &T)> {
+        self.map.iter().map(|(i, v)| (I::from_usize(*i), v))
+    }
+
+    /// An iterator visiting all elements in ascending order of their indices, with mutable
+    /// references to the values.
+    /// The index is returned along with the value.
+    /// The iterator skips indices that do not have a corresponding value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_index_vec::FixedIndexVec;
+    ///
+    /// let mut vec: FixedIndexVec<i32> = vec![1, 2, 3].into();
+    /// for (_, v) in vec.iter_mut() {
+    ///     *v *= 2;
+    /// }
+    /// assert_eq!(vec.get(1), Some(&4));
+    /// ```
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (I, &mut T)> {
+        self.map.iter_mut().map(|(i, v)| (I::from_usize(*i), v))
+    }
+
+    /// An iterator visiting all values in ascending order of their indices.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_index_vec::FixedIndexVec;
+    ///
+    /// let vec: FixedIndexVec<i32> = vec![1, 2, 3].into();
+    /// let values: Vec<&i32> = vec.values().collect();
+    /// assert_eq!(values, vec![&1, &2, &3]);
+    /// ```
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.map.values()
+    }
+
+    /// An iterator visiting all values in ascending order of their indices, yielding mutable
+    /// references.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_index_vec::FixedIndexVec;
+    ///
+    /// let mut vec: FixedIndexVec<i32> = vec![1, 2, 3].into();
+    /// for v in vec.values_mut() {
+    ///     *v *= 2;
+    /// }
+    /// assert_eq!(vec.get(0), Some(&2));
+    /// ```
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.map.values_mut()
+    }
+
+    /// Retains only the elements for which `f` returns `true`, removing the rest.
+    /// Like `Vec::retain`, but `f` is also given each element's index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_index_vec::FixedIndexVec;
+    ///
+    /// let mut vec: FixedIndexVec<i32> = vec![1, 2, 3, 4].into();
+    /// vec.retain(|_, v| *v % 2 == 0);
+    /// assert_eq!(vec.get(0), None);
+    /// assert_eq!(vec.get(1), Some(&2));
+    /// assert_eq!(vec.get(3), Some(&4));
+    /// ```
+    pub fn retain<F: FnMut(I, &mut T) -> bool>(&mut self, mut f: F) {
+        self.map.retain(|i, v| f(I::from_usize(*i), v));
     }
 
     /// Returns the number of elements in the `FixedIndexVec`.
@@ -256,8 +517,8 @@ impl<T> FixedIndexVec<T> {
     /// vec.remove(1);
     /// assert_eq!(vec.next_index(), 3);
     /// ```
-    pub fn next_index(&self) -> usize {
-        self.next_index
+    pub fn next_index(&self) -> I {
+        I::from_usize(self.next_index)
     }
 
     /// Returns the index and a reference to the element at the smallest populated index, or `None`
@@ -277,7 +538,7 @@ impl<T> FixedIndexVec<T> {
     ///
     /// let vec: FixedIndexVec<i32> = FixedIndexVec::new();
     /// assert_eq!(vec.first(), None);
-    pub fn first(&self) -> Option<(usize, &T)> {
+    pub fn first(&self) -> Option<(I, &T)> {
         self.iter().next()
     }
 
@@ -299,21 +560,153 @@ impl<T> FixedIndexVec<T> {
     /// let vec: FixedIndexVec<i32> = FixedIndexVec::new();
     /// assert_eq!(vec.last(), None);
     /// ```
-    pub fn last(&self) -> Option<(usize, &T)> {
+    pub fn last(&self) -> Option<(I, &T)> {
         self.iter().last()
     }
+
+    /// Gets the entry at the given index for in-place access, insertion, or removal.
+    ///
+    /// If `index` is vacant and a value is inserted into it, `next_index` is advanced to
+    /// `index + 1` if it wasn't already past that point, so subsequent `push`es won't collide
+    /// with it.
+    ///
+    /// # Panics
+    ///
+    /// Inserting into a vacant entry panics if the `FixedIndexVec` is at capacity, the same as
+    /// `push`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_index_vec::FixedIndexVec;
+    ///
+    /// let mut vec: FixedIndexVec<i32> = FixedIndexVec::new();
+    /// *vec.entry(0).or_insert(0) += 1;
+    /// *vec.entry(0).or_insert(0) += 1;
+    /// assert_eq!(vec.get(0), Some(&2));
+    /// ```
+    pub fn entry(&mut self, index: I) -> Entry<'_, T, I> {
+        let index = index.into_usize();
+        let len = self.map.len();
+        let capacity = self.capacity;
+        match self.map.entry(index) {
+            btree_map::Entry::Occupied(entry) => Entry::Occupied(OccupiedEntry { inner: entry }),
+            btree_map::Entry::Vacant(entry) => Entry::Vacant(VacantEntry {
+                index,
+                inner: entry,
+                next_index: &mut self.next_index,
+                capacity,
+                len,
+                _marker: PhantomData,
+            }),
+        }
+    }
+}
+
+/// A view into a single entry of a [`FixedIndexVec`], which may be either vacant or occupied.
+///
+/// This is constructed via [`FixedIndexVec::entry`].
+pub enum Entry<'a, T, I> {
+    /// An occupied entry.
+    Occupied(OccupiedEntry<'a, T>),
+    /// A vacant entry.
+    Vacant(VacantEntry<'a, T, I>),
+}
+
+impl<'a, T, I: Idx> Entry<'a, T, I> {
+    /// Ensures a value is present at this entry, inserting `default` if it was vacant, then
+    /// returns a mutable reference to the value.
+    pub fn or_insert(self, default: T) -> &'a mut T {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    /// Ensures a value is present at this entry, inserting the result of `default` if it was
+    /// vacant, then returns a mutable reference to the value.
+    pub fn or_insert_with<F: FnOnce() -> T>(self, default: F) -> &'a mut T {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
 }
 
-impl<T> std::ops::Index<usize> for FixedIndexVec<T> {
+/// An occupied entry, returned by [`FixedIndexVec::entry`].
+pub struct OccupiedEntry<'a, T> {
+    inner: btree_map::OccupiedEntry<'a, usize, T>,
+}
+
+impl<'a, T> OccupiedEntry<'a, T> {
+    /// Returns a reference to the value in the entry.
+    pub fn get(&self) -> &T {
+        self.inner.get()
+    }
+
+    /// Returns a mutable reference to the value in the entry.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.inner.get_mut()
+    }
+
+    /// Converts the entry into a mutable reference to the value, bound to the lifetime of the
+    /// `FixedIndexVec`.
+    pub fn into_mut(self) -> &'a mut T {
+        self.inner.into_mut()
+    }
+
+    /// Removes the value from the `FixedIndexVec` and returns it.
+    pub fn remove(self) -> T {
+        self.inner.remove()
+    }
+}
+
+/// A vacant entry, returned by [`FixedIndexVec::entry`].
+pub struct VacantEntry<'a, T, I> {
+    index: usize,
+    inner: btree_map::VacantEntry<'a, usize, T>,
+    next_index: &'a mut usize,
+    capacity: Option<usize>,
+    len: usize,
+    _marker: PhantomData<I>,
+}
+
+impl<'a, T, I: Idx> VacantEntry<'a, T, I> {
+    /// Inserts `value` at this entry's index, advancing `next_index` past it if necessary, and
+    /// returns a mutable reference to the inserted value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `FixedIndexVec` is at capacity.
+    pub fn insert(self, value: T) -> &'a mut T {
+        if let Some(capacity) = self.capacity {
+            if self.len >= capacity {
+                panic!("FixedIndexVec is at capacity");
+            }
+        }
+        if *self.next_index <= self.index {
+            *self.next_index = self.index + 1;
+        }
+        self.inner.insert(value)
+    }
+}
+
+impl<T, I: Idx> std::ops::Index<I> for FixedIndexVec<T, I> {
     type Output = T;
 
-    fn index(&self, index: usize) -> &T {
+    fn index(&self, index: I) -> &T {
         self.get(index).unwrap()
     }
 }
 
-impl<T> FromIterator<T> for FixedIndexVec<T> {
-    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> FixedIndexVec<T> {
+impl<T, I: Idx> std::ops::IndexMut<I> for FixedIndexVec<T, I> {
+    fn index_mut(&mut self, index: I) -> &mut T {
+        self.get_mut(index).unwrap()
+    }
+}
+
+impl<T, I: Idx> FromIterator<T> for FixedIndexVec<T, I> {
+    fn from_iter<It: IntoIterator<Item = T>>(iter: It) -> FixedIndexVec<T, I> {
         let mut map = BTreeMap::new();
         for (i, v) in iter.into_iter().enumerate() {
             map.insert(i, v);
@@ -321,12 +714,331 @@ impl<T> FromIterator<T> for FixedIndexVec<T> {
         FixedIndexVec {
             next_index: map.len(),
             map,
+            capacity: None,
+            _marker: PhantomData,
         }
     }
 }
 
-impl<T> From<Vec<T>> for FixedIndexVec<T> {
-    fn from(vec: Vec<T>) -> FixedIndexVec<T> {
+impl<T, I: Idx> From<Vec<T>> for FixedIndexVec<T, I> {
+    fn from(vec: Vec<T>) -> FixedIndexVec<T, I> {
         vec.into_iter().collect()
     }
 }
+
+/// An opaque handle to an element of a [`GenerationalIndexVec`].
+///
+/// A `Handle` pairs a slot index with the generation that was current when the value was
+/// inserted. Once that slot is removed and reused by a later `push`, its generation is bumped,
+/// so a stale handle obtained before the reuse will no longer resolve to anything: this is what
+/// defeats the ABA problem.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Handle {
+    index: usize,
+    generation: u32,
+}
+
+/// A generational companion to [`FixedIndexVec`] that reuses vacated slots instead of letting
+/// indices grow unboundedly. `FixedIndexVec` remains the "no-reuse" variant, whose indices only
+/// ever increase.
+///
+/// Removed slots are tracked on a free list and handed back out by later `push`es, with each
+/// slot's generation counter bumped on every removal. Callers identify elements with an opaque
+/// [`Handle`] rather than a raw index, so a handle obtained before a slot was reused can never
+/// be mistaken for a handle to whatever was pushed into that slot afterwards.
+///
+/// # Examples
+///
+/// ```
+/// use fixed_index_vec::GenerationalIndexVec;
+///
+/// let mut vec = GenerationalIndexVec::new();
+/// let stale = vec.push("value1".to_string());
+/// vec.remove(stale);
+///
+/// let fresh = vec.push("value2".to_string());
+/// assert_eq!(vec.get(fresh), Some(&"value2".to_string()));
+/// assert_eq!(vec.get(stale), None);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct GenerationalIndexVec<T> {
+    map: BTreeMap<usize, (u32, T)>,
+    generations: Vec<u32>,
+    free: Vec<usize>,
+}
+
+impl<T> GenerationalIndexVec<T> {
+    /// Creates an empty `GenerationalIndexVec`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_index_vec::GenerationalIndexVec;
+    /// let vec: GenerationalIndexVec<i32> = GenerationalIndexVec::new();
+    /// ```
+    pub fn new() -> GenerationalIndexVec<T> {
+        GenerationalIndexVec {
+            map: BTreeMap::new(),
+            generations: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+
+    /// Inserts an element, reusing the most recently vacated slot if there is one and otherwise
+    /// appending a new slot, and returns a `Handle` to it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_index_vec::GenerationalIndexVec;
+    ///
+    /// let mut vec = GenerationalIndexVec::new();
+    /// let handle = vec.push(1);
+    /// assert_eq!(vec.get(handle), Some(&1));
+    /// ```
+    pub fn push(&mut self, value: T) -> Handle {
+        let index = self.free.pop().unwrap_or_else(|| {
+            self.generations.push(0);
+            self.generations.len() - 1
+        });
+        let generation = self.generations[index];
+        self.map.insert(index, (generation, value));
+        Handle { index, generation }
+    }
+
+    /// Removes the element referenced by `handle`, if `handle` is still valid, returning it or
+    /// `None` if the slot is empty or has already been reused.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_index_vec::GenerationalIndexVec;
+    ///
+    /// let mut vec = GenerationalIndexVec::new();
+    /// let handle = vec.push(1);
+    /// assert_eq!(vec.remove(handle), Some(1));
+    /// assert_eq!(vec.remove(handle), None);
+    /// ```
+    pub fn remove(&mut self, handle: Handle) -> Option<T> {
+        match self.map.entry(handle.index) {
+            btree_map::Entry::Occupied(entry) if entry.get().0 == handle.generation => {
+                let (_, value) = entry.remove();
+                self.generations[handle.index] = self.generations[handle.index].wrapping_add(1);
+                self.free.push(handle.index);
+                Some(value)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns a reference to the element referenced by `handle`, if `handle` is still valid,
+    /// or `None` if the slot is empty or has already been reused.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_index_vec::GenerationalIndexVec;
+    ///
+    /// let mut vec = GenerationalIndexVec::new();
+    /// let handle = vec.push(1);
+    /// assert_eq!(vec.get(handle), Some(&1));
+    /// ```
+    pub fn get(&self, handle: Handle) -> Option<&T> {
+        self.map
+            .get(&handle.index)
+            .filter(|(generation, _)| *generation == handle.generation)
+            .map(|(_, value)| value)
+    }
+
+    /// Returns the number of elements in the `GenerationalIndexVec`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_index_vec::GenerationalIndexVec;
+    ///
+    /// let mut vec = GenerationalIndexVec::new();
+    /// vec.push(1);
+    /// assert_eq!(vec.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if the `GenerationalIndexVec` contains no elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fixed_index_vec::GenerationalIndexVec;
+    ///
+    /// let vec: GenerationalIndexVec<i32> = GenerationalIndexVec::new();
+    /// assert_eq!(vec.is_empty(), true);
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
+
+impl<T> std::ops::Index<Handle> for GenerationalIndexVec<T> {
+    type Output = T;
+
+    fn index(&self, handle: Handle) -> &T {
+        self.get(handle).unwrap()
+    }
+}
+
+/// A sparse, sequence-based serde representation for [`FixedIndexVec`].
+///
+/// `FixedIndexVec` has no `Serialize`/`Deserialize` impls of its own, since deriving them would
+/// either leak its internals or, via `BTreeMap`'s default map encoding, serialize sparse data
+/// poorly. Following [`indexmap`](https://docs.rs/indexmap)'s `serde_seq` module, this module
+/// instead emits a compact sequence preceded by `next_index` and `capacity`, followed by the
+/// `(index, value)` pairs, and can be attached to a `FixedIndexVec` field with
+/// `#[serde(with = "fixed_index_vec::serde_seq")]`.
+///
+/// # Examples
+///
+/// ```
+/// use fixed_index_vec::FixedIndexVec;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Container {
+///     #[serde(with = "fixed_index_vec::serde_seq")]
+///     values: FixedIndexVec<i32>,
+/// }
+/// ```
+#[cfg(feature = "serde")]
+pub mod serde_seq {
+    use super::{FixedIndexVec, Idx};
+    use serde::de::{self, SeqAccess, Visitor};
+    use serde::ser::SerializeSeq;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::collections::BTreeMap;
+    use std::fmt;
+    use std::marker::PhantomData;
+
+    /// Serializes a `FixedIndexVec` as a sequence of `(index, value)` pairs, preceded by its
+    /// `next_index` and `capacity`.
+    pub fn serialize<T, I, S>(vec: &FixedIndexVec<T, I>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize,
+        I: Idx,
+        S: Serializer,
+    {
+        serialize_seq(vec, serializer)
+    }
+
+    /// Serializes a `FixedIndexVec` as a sequence of `(index, value)` pairs, preceded by its
+    /// `next_index` and `capacity`.
+    pub fn serialize_seq<T, I, S>(
+        vec: &FixedIndexVec<T, I>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize,
+        I: Idx,
+        S: Serializer,
+    {
+        let mut seq = serializer.serialize_seq(Some(vec.len() + 2))?;
+        seq.serialize_element(&vec.next_index().into_usize())?;
+        seq.serialize_element(&vec.capacity())?;
+        for (index, value) in vec.iter() {
+            seq.serialize_element(&(index.into_usize(), value))?;
+        }
+        seq.end()
+    }
+
+    /// Deserializes a `FixedIndexVec` from the sparse sequence format produced by
+    /// [`serialize_seq`].
+    pub fn deserialize<'de, T, I, D>(deserializer: D) -> Result<FixedIndexVec<T, I>, D::Error>
+    where
+        T: Deserialize<'de>,
+        I: Idx,
+        D: Deserializer<'de>,
+    {
+        deserialize_seq(deserializer)
+    }
+
+    /// Deserializes a `FixedIndexVec` from the sparse sequence format produced by
+    /// [`serialize_seq`].
+    ///
+    /// Rejects malformed input: the `(index, value)` pairs must have strictly increasing
+    /// indices, the leading `next_index` must be greater than the largest index present, and if
+    /// a `capacity` was recorded, the number of pairs must not exceed it.
+    pub fn deserialize_seq<'de, T, I, D>(deserializer: D) -> Result<FixedIndexVec<T, I>, D::Error>
+    where
+        T: Deserialize<'de>,
+        I: Idx,
+        D: Deserializer<'de>,
+    {
+        struct SeqVisitor<T, I>(PhantomData<(T, I)>);
+
+        impl<'de, T, I> Visitor<'de> for SeqVisitor<T, I>
+        where
+            T: Deserialize<'de>,
+            I: Idx,
+        {
+            type Value = FixedIndexVec<T, I>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(
+                    "a next_index and capacity followed by a sequence of strictly increasing (index, value) pairs",
+                )
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let next_index: usize = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+                let capacity: Option<usize> = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+
+                let mut map = BTreeMap::new();
+                let mut last_index: Option<usize> = None;
+                while let Some((index, value)) = seq.next_element::<(usize, T)>()? {
+                    if let Some(last_index) = last_index {
+                        if index <= last_index {
+                            return Err(de::Error::custom(format!(
+                                "indices must be strictly increasing, got {index} after {last_index}"
+                            )));
+                        }
+                    }
+                    last_index = Some(index);
+                    map.insert(index, value);
+                }
+
+                if let Some(last_index) = last_index {
+                    if next_index <= last_index {
+                        return Err(de::Error::custom(format!(
+                            "next_index ({next_index}) must be greater than the largest index ({last_index})"
+                        )));
+                    }
+                }
+
+                if let Some(capacity) = capacity {
+                    if map.len() > capacity {
+                        return Err(de::Error::custom(format!(
+                            "{} entries exceed the recorded capacity ({capacity})",
+                            map.len()
+                        )));
+                    }
+                }
+
+                Ok(FixedIndexVec {
+                    map,
+                    next_index,
+                    capacity,
+                    _marker: PhantomData,
+                })
+            }
+        }
+
+        deserializer.deserialize_seq(SeqVisitor(PhantomData))
+    }
+}